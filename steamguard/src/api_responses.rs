@@ -0,0 +1,85 @@
+use serde::Deserialize;
+
+/// Response from `/login/getrsakey/`. Contains the public key Steam wants the password encrypted with before it's sent to `/login/dologin`.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct RsaResponse {
+	pub success: bool,
+	pub publickey_exp: String,
+	pub publickey_mod: String,
+	pub timestamp: String,
+	pub token_gid: String,
+}
+
+/// A single pending mobile confirmation, as returned by `SteamApiClient::get_confirmations`. Covers both trade offers and market listings.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Confirmation {
+	pub id: String,
+	#[serde(rename = "type")]
+	pub conf_type: ConfirmationType,
+	pub creator_id: String,
+	pub nonce: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationType {
+	Generic,
+	Trade,
+	MarketSellTransaction,
+	Unknown(i32),
+}
+
+impl From<i32> for ConfirmationType {
+	fn from(v: i32) -> Self {
+		match v {
+			1 => ConfirmationType::Generic,
+			2 => ConfirmationType::Trade,
+			3 => ConfirmationType::MarketSellTransaction,
+			other => ConfirmationType::Unknown(other),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for ConfirmationType {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		Ok(ConfirmationType::from(i32::deserialize(deserializer)?))
+	}
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct GetConfirmationsResponse {
+	pub success: bool,
+	pub conf: Vec<Confirmation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct SendConfirmationResponse {
+	pub success: bool,
+}
+
+/// Response from `/IMobileAuthService/GetWGToken/v0001`, used to re-derive web session cookies
+/// from a still-valid OAuth token.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct RefreshSessionResponse {
+	pub token: String,
+	pub token_secure: String,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_confirmation_type_from_known_values() {
+		assert_eq!(ConfirmationType::from(1), ConfirmationType::Generic);
+		assert_eq!(ConfirmationType::from(2), ConfirmationType::Trade);
+		assert_eq!(ConfirmationType::from(3), ConfirmationType::MarketSellTransaction);
+	}
+
+	#[test]
+	fn test_confirmation_type_from_unknown_value() {
+		assert_eq!(ConfirmationType::from(99), ConfirmationType::Unknown(99));
+	}
+}
@@ -7,12 +7,15 @@ use reqwest::{
 	header::{HeaderMap, HeaderName, HeaderValue, SET_COOKIE},
 	Url,
 };
+use hmac::{Hmac, Mac, NewMac};
+use rsa::{BigUint, PaddingScheme, PublicKey, RsaPublicKey};
 use secrecy::{CloneableSecret, DebugSecret, ExposeSecret, SerializableSecret};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha1::Sha1;
 use std::iter::FromIterator;
 use std::str::FromStr;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use zeroize::Zeroize;
 
 lazy_static! {
@@ -20,6 +23,27 @@ lazy_static! {
 	static ref STEAM_API_BASE: String = "https://api.steampowered.com".into();
 }
 
+type HmacSha1 = Hmac<Sha1>;
+
+/// Builds the `k` parameter Steam expects on every `/mobileconf/*` request: `HMAC-SHA1(identity_secret, time || tag)`, base64 encoded.
+/// `tag` must be one of `"conf"`, `"details"`, `"allow"`, or `"cancel"`.
+fn generate_confirmation_hash_for_time(
+	time: u64,
+	tag: &str,
+	identity_secret: &str,
+) -> anyhow::Result<String> {
+	let decoded_secret = base64::decode(identity_secret)?;
+
+	let mut buffer = time.to_be_bytes().to_vec();
+	buffer.extend(tag.as_bytes());
+
+	let mut mac = HmacSha1::new_from_slice(&decoded_secret)?;
+	mac.update(&buffer);
+	let result = mac.finalize().into_bytes();
+
+	Ok(base64::encode(result))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Zeroize)]
 #[zeroize(drop)]
 pub struct Session {
@@ -41,6 +65,24 @@ impl SerializableSecret for Session {}
 impl CloneableSecret for Session {}
 impl DebugSecret for Session {}
 
+/// Encrypts `password` with the RSA key Steam handed back from `get_rsa_key`, PKCS#1 v1.5 padded and base64 encoded, ready to be passed as the `password` field of `/login/dologin`.
+fn encrypt_password(rsa: &RsaResponse, password: &str) -> anyhow::Result<String> {
+	let key = RsaPublicKey::new(
+		BigUint::from_bytes_be(&hex::decode(&rsa.publickey_mod)?),
+		BigUint::from_bytes_be(&hex::decode(&rsa.publickey_exp)?),
+	)?;
+
+	let mut password_bytes = password.as_bytes().to_vec();
+	let encrypted = key.encrypt(
+		&mut rand::thread_rng(),
+		PaddingScheme::new_pkcs1v15_encrypt(),
+		&password_bytes,
+	);
+	password_bytes.zeroize();
+
+	Ok(base64::encode(encrypted?))
+}
+
 /// Queries Steam for the current time.
 ///
 /// Endpoint: `/ITwoFactorService/QueryTime/v0001`
@@ -72,6 +114,140 @@ pub fn get_server_time() -> anyhow::Result<QueryTimeResponse> {
 	return Ok(resp.response);
 }
 
+/// Returns whether a freshly probed offset has jumped by more than `large_time_jink` seconds
+/// compared to the previously measured offset, which `TimeAligner` treats as a clock reset rather
+/// than ordinary drift.
+fn is_clock_jink(new_offset: i64, previous_offset: i64, large_time_jink: i64) -> bool {
+	(new_offset - previous_offset).abs() > large_time_jink
+}
+
+/// Tracks the offset between Steam's server clock and the local clock, so that TOTP codes can be
+/// generated against Steam's notion of time without hitting `get_server_time` on every call.
+///
+/// The offset is re-measured once `adjusted_time_probe_frequency_seconds` (as hinted by Steam)
+/// has elapsed since the last probe. If a fresh probe shows the offset jumping by more than
+/// `large_time_jink`, the local clock is assumed to have been reset rather than drifted, and a
+/// warning is logged.
+#[derive(Debug)]
+pub struct TimeAligner {
+	offset: i64,
+	last_probed_at: Instant,
+	probe_frequency: Duration,
+}
+
+impl TimeAligner {
+	/// Builds a `TimeAligner`, probing Steam once up front to seed the offset.
+	pub fn new() -> anyhow::Result<Self> {
+		let mut aligner = TimeAligner {
+			offset: 0,
+			last_probed_at: Instant::now(),
+			probe_frequency: Duration::from_secs(3600),
+		};
+		aligner.probe(None)?;
+		Ok(aligner)
+	}
+
+	fn probe(&mut self, previous_offset: Option<i64>) -> anyhow::Result<()> {
+		let probed_at = Instant::now();
+		let time = get_server_time()?;
+		let local_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+		let server_time: i64 = time.server_time.parse()?;
+		let new_offset = server_time - local_time;
+
+		if let Some(previous_offset) = previous_offset {
+			let large_time_jink: i64 = time.large_time_jink.parse().unwrap_or(86400);
+			if is_clock_jink(new_offset, previous_offset, large_time_jink) {
+				warn!(
+					"local clock jumped by {} seconds since the last time probe; treating as a clock reset",
+					new_offset - previous_offset
+				);
+			}
+		}
+
+		self.offset = new_offset;
+		self.last_probed_at = probed_at;
+		self.probe_frequency = Duration::from_secs(time.adjusted_time_probe_frequency_seconds);
+
+		Ok(())
+	}
+
+	/// Returns the current UNIX time, corrected for the measured offset between the local clock
+	/// and Steam's server clock. Automatically re-probes Steam if the cached offset is stale.
+	pub fn aligned_time(&mut self) -> anyhow::Result<u64> {
+		if self.last_probed_at.elapsed() >= self.probe_frequency {
+			self.probe(Some(self.offset))?;
+		}
+
+		let local_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+		Ok((local_time + self.offset) as u64)
+	}
+}
+
+/// Inspects a `login` response for a captcha challenge. If Steam is demanding one, returns the
+/// `captcha_gid` to pass to `SteamApiClient::get_captcha_image`, then re-drive `login` with
+/// `captcha_gid` and the user-entered `captcha_text` set.
+pub fn login_needs_captcha(resp: &LoginResponse) -> Option<&str> {
+	if resp.captcha_needed {
+		resp.captcha_gid.as_deref()
+	} else {
+		None
+	}
+}
+
+/// Shared between `SteamApiClient` and `AsyncSteamApiClient` so both stay behavior-identical:
+/// pulls the `sessionid` cookie that Steam hands back out of the jar.
+fn extract_session_id(cookies: &reqwest::cookie::Jar) -> Option<String> {
+	let raw_cookies = cookies.cookies(&STEAM_COOKIE_URL)?;
+	let all_cookies = raw_cookies.to_str().ok()?;
+	for cookie in all_cookies
+		.split(";")
+		.map(|s| cookie::Cookie::parse(s).unwrap())
+	{
+		if cookie.name() == "sessionid" {
+			return Some(cookie.value().into());
+		}
+	}
+	None
+}
+
+/// Shared between `SteamApiClient` and `AsyncSteamApiClient`: turns the `OAuthData` a login
+/// response hands back, plus the session id pulled from cookies, into a storable `Session`.
+fn build_session_from_oauth(data: &OAuthData, session_id: String) -> Session {
+	Session {
+		token: data.oauth_token.clone(),
+		steam_id: data.steamid.parse().unwrap(),
+		steam_login: format!("{}%7C%7C{}", data.steamid, data.wgtoken),
+		steam_login_secure: format!("{}%7C%7C{}", data.steamid, data.wgtoken_secure),
+		session_id,
+		web_cookie: Some(data.webcookie.clone()),
+	}
+}
+
+/// Shared between `SteamApiClient` and `AsyncSteamApiClient`: copies any `Set-Cookie` headers on
+/// a response into the cookie jar.
+fn save_cookies_from_response(cookies: &reqwest::cookie::Jar, headers: &HeaderMap) {
+	for c in headers.get_all(SET_COOKIE) {
+		c.to_str()
+			.into_iter()
+			.for_each(|cookie_str| cookies.add_cookie_str(cookie_str, &STEAM_COOKIE_URL));
+	}
+}
+
+/// Shared between `SteamApiClient` and `AsyncSteamApiClient`: primes the cookie jar with the
+/// static mobile-app cookies every request needs, plus the `sessionid` cookie if a session is
+/// present, ahead of building the request's `Cookie` header.
+fn prime_request_cookies(cookies: &reqwest::cookie::Jar, session: Option<&Session>) {
+	cookies.add_cookie_str("mobileClientVersion=0 (2.1.3)", &STEAM_COOKIE_URL);
+	cookies.add_cookie_str("mobileClient=android", &STEAM_COOKIE_URL);
+	cookies.add_cookie_str("Steam_Language=english", &STEAM_COOKIE_URL);
+	if let Some(session) = session {
+		cookies.add_cookie_str(
+			format!("sessionid={}", session.session_id).as_str(),
+			&STEAM_COOKIE_URL,
+		);
+	}
+}
+
 /// Provides raw access to the Steam API. Handles cookies, some deserialization, etc. to make it easier. It covers `ITwoFactorService` from the Steam web API, and some mobile app specific api endpoints.
 #[derive(Debug)]
 pub struct SteamApiClient {
@@ -98,40 +274,19 @@ impl SteamApiClient {
 
 	fn build_session(&self, data: &OAuthData) -> Session {
 		trace!("SteamApiClient::build_session");
-		return Session {
-			token: data.oauth_token.clone(),
-			steam_id: data.steamid.parse().unwrap(),
-			steam_login: format!("{}%7C%7C{}", data.steamid, data.wgtoken),
-			steam_login_secure: format!("{}%7C%7C{}", data.steamid, data.wgtoken_secure),
-			session_id: self
-				.extract_session_id()
+		build_session_from_oauth(
+			data,
+			self.extract_session_id()
 				.expect("failed to extract session id from cookies"),
-			web_cookie: Some(data.webcookie.clone()),
-		};
+		)
 	}
 
 	fn extract_session_id(&self) -> Option<String> {
-		let cookies = self.cookies.cookies(&STEAM_COOKIE_URL).unwrap();
-		let all_cookies = cookies.to_str().unwrap();
-		for cookie in all_cookies
-			.split(";")
-			.map(|s| cookie::Cookie::parse(s).unwrap())
-		{
-			if cookie.name() == "sessionid" {
-				return Some(cookie.value().into());
-			}
-		}
-		return None;
+		extract_session_id(&self.cookies)
 	}
 
 	pub fn save_cookies_from_response(&mut self, response: &reqwest::blocking::Response) {
-		let set_cookie_iter = response.headers().get_all(SET_COOKIE);
-
-		for c in set_cookie_iter {
-			c.to_str()
-				.into_iter()
-				.for_each(|cookie_str| self.cookies.add_cookie_str(cookie_str, &STEAM_COOKIE_URL));
-		}
+		save_cookies_from_response(&self.cookies, response.headers());
 	}
 
 	pub fn request<U: reqwest::IntoUrl + std::fmt::Display>(
@@ -140,18 +295,10 @@ impl SteamApiClient {
 		url: U,
 	) -> RequestBuilder {
 		trace!("making request: {} {}", method, url);
-		self.cookies
-			.add_cookie_str("mobileClientVersion=0 (2.1.3)", &STEAM_COOKIE_URL);
-		self.cookies
-			.add_cookie_str("mobileClient=android", &STEAM_COOKIE_URL);
-		self.cookies
-			.add_cookie_str("Steam_Language=english", &STEAM_COOKIE_URL);
-		if let Some(session) = &self.session {
-			self.cookies.add_cookie_str(
-				format!("sessionid={}", session.expose_secret().session_id).as_str(),
-				&STEAM_COOKIE_URL,
-			);
-		}
+		prime_request_cookies(
+			&self.cookies,
+			self.session.as_ref().map(|s| s.expose_secret()),
+		);
 
 		self.client
 			.request(method, url)
@@ -180,17 +327,101 @@ impl SteamApiClient {
 		Ok(())
 	}
 
+	/// Pings an authenticated community page to check whether this client's session cookies are
+	/// still accepted by Steam; the OAuth token is not sent. Steam redirects unauthenticated requests to the
+	/// login page, so a redirect there means the session has expired. Useful for long-lived
+	/// processes to cheaply revalidate before a batch of operations instead of blindly
+	/// re-logging-in.
+	pub fn is_session_valid(&self) -> bool {
+		match self.get("https://steamcommunity.com/my/profile").send() {
+			Ok(resp) => !resp.url().as_str().contains("/login"),
+			Err(_) => false,
+		}
+	}
+
+	/// Re-derives the web session cookies from the stored OAuth token and rewrites this client's
+	/// `Secret<Session>` in place. Lets a caller recover from an expired session without a full
+	/// username/password login.
+	///
+	/// Host: api.steampowered.com
+	/// Endpoint: POST /IMobileAuthService/GetWGToken/v0001
+	pub fn refresh_session(&mut self) -> anyhow::Result<()> {
+		ensure!(matches!(self.session, Some(_)));
+		let old_session = self.session.as_ref().unwrap().expose_secret().clone();
+
+		let params = hashmap! {
+			"access_token" => old_session.token.clone(),
+		};
+
+		let resp = self
+			.post(format!(
+				"{}/IMobileAuthService/GetWGToken/v0001",
+				STEAM_API_BASE.to_string()
+			))
+			.form(&params)
+			.send()?;
+		let text = resp.text()?;
+		trace!("raw refresh_session response: {}", text);
+
+		let resp: SteamApiResponse<RefreshSessionResponse> = serde_json::from_str(text.as_str())?;
+
+		self.session = Some(secrecy::Secret::new(Session {
+			steam_login: format!("{}%7C%7C{}", old_session.steam_id, resp.response.token),
+			steam_login_secure: format!(
+				"{}%7C%7C{}",
+				old_session.steam_id, resp.response.token_secure
+			),
+			..old_session
+		}));
+
+		Ok(())
+	}
+
+	/// Fetches the RSA public key Steam wants this account's password encrypted with before it can be sent to `dologin`.
+	///
+	/// Endpoint: POST /login/getrsakey/
+	fn get_rsa_key(&self, username: &str) -> anyhow::Result<RsaResponse> {
+		let params = hashmap! {
+			"donotcache" => format!(
+				"{}",
+				SystemTime::now()
+					.duration_since(UNIX_EPOCH)
+					.unwrap()
+					.as_secs()
+					* 1000
+			),
+			"username" => username.to_string(),
+		};
+
+		let resp = self
+			.post("https://steamcommunity.com/login/getrsakey/")
+			.form(&params)
+			.send()?;
+		let text = resp.text()?;
+		trace!("raw get_rsa_key response: {}", text);
+
+		let resp: RsaResponse = serde_json::from_str(text.as_str())?;
+		ensure!(resp.success, "steam did not provide an rsa key for this account");
+
+		Ok(resp)
+	}
+
 	/// Endpoint: POST /login/dologin
 	pub fn login(
 		&mut self,
 		username: String,
-		encrypted_password: String,
+		password: String,
 		twofactor_code: String,
 		email_code: String,
 		captcha_gid: String,
 		captcha_text: String,
-		rsa_timestamp: String,
 	) -> anyhow::Result<LoginResponse> {
+		let rsa = self.get_rsa_key(&username)?;
+		let mut password = password;
+		let encrypted_password_result = encrypt_password(&rsa, &password);
+		password.zeroize();
+		let encrypted_password = encrypted_password_result?;
+
 		let params = hashmap! {
 			"donotcache" => format!(
 				"{}",
@@ -206,7 +437,7 @@ impl SteamApiClient {
 			"emailauth" => email_code,
 			"captchagid" => captcha_gid,
 			"captcha_text" => captcha_text,
-			"rsatimestamp" => rsa_timestamp,
+			"rsatimestamp" => rsa.timestamp.clone(),
 			"remember_login" => "true".into(),
 			"oauth_client_id" => "DE45CD61".into(),
 			"oauth_scope" => "read_profile write_profile read_client write_client".into(),
@@ -229,6 +460,23 @@ impl SteamApiClient {
 		return Ok(login_resp);
 	}
 
+	/// Fetches the raw image bytes for a captcha challenge that `login` reported via
+	/// `captcha_gid` (see `login_needs_captcha`), so the caller can display it to the user and
+	/// re-drive `login` with the entered text.
+	///
+	/// Host: steamcommunity.com
+	/// Endpoint: GET /login/rendercaptcha/
+	pub fn get_captcha_image(&self, gid: &str) -> anyhow::Result<Vec<u8>> {
+		let resp = self
+			.get(format!(
+				"https://steamcommunity.com/login/rendercaptcha/?gid={}",
+				gid
+			))
+			.send()?;
+
+		Ok(resp.bytes()?.to_vec())
+	}
+
 	/// A secondary step in the login flow. Does not seem to always be needed?
 	/// Endpoints: provided by `login()`
 	pub fn transfer_login(&mut self, login_resp: LoginResponse) -> anyhow::Result<OAuthData> {
@@ -474,4 +722,521 @@ impl SteamApiClient {
 
 		return Ok(resp.response);
 	}
+
+	/// Fetches the list of pending mobile confirmations (trade offers, market listings, etc.) for the logged in account.
+	///
+	/// Host: steamcommunity.com
+	/// Endpoint: GET /mobileconf/getlist
+	pub fn get_confirmations(
+		&self,
+		device_id: &str,
+		identity_secret: &str,
+	) -> anyhow::Result<Vec<Confirmation>> {
+		ensure!(matches!(self.session, Some(_)));
+		let steamid = self.session.as_ref().unwrap().expose_secret().steam_id;
+		let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+		let hash = generate_confirmation_hash_for_time(time, "conf", identity_secret)?;
+
+		let resp = self
+			.get("https://steamcommunity.com/mobileconf/getlist")
+			.query(&[
+				("p", device_id),
+				("a", steamid.to_string().as_str()),
+				("k", hash.as_str()),
+				("t", time.to_string().as_str()),
+				("m", "react"),
+				("tag", "conf"),
+			])
+			.send()?;
+		let text = resp.text()?;
+		trace!("raw get_confirmations response: {}", text);
+
+		let resp: GetConfirmationsResponse = serde_json::from_str(text.as_str())?;
+		ensure!(resp.success, "steam rejected the confirmation list request");
+
+		Ok(resp.conf)
+	}
+
+	/// Accepts or cancels a single pending mobile confirmation.
+	///
+	/// Host: steamcommunity.com
+	/// Endpoint: POST /mobileconf/ajaxop
+	pub fn respond_to_confirmation(
+		&self,
+		device_id: &str,
+		identity_secret: &str,
+		conf: &Confirmation,
+		accept: bool,
+	) -> anyhow::Result<()> {
+		ensure!(matches!(self.session, Some(_)));
+		let steamid = self.session.as_ref().unwrap().expose_secret().steam_id;
+		let op = if accept { "allow" } else { "cancel" };
+		let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+		let hash = generate_confirmation_hash_for_time(time, op, identity_secret)?;
+
+		let params = hashmap! {
+			"op" => op,
+			"p" => device_id,
+			"a" => steamid.to_string().as_str(),
+			"k" => hash.as_str(),
+			"t" => time.to_string().as_str(),
+			"m" => "react",
+			"tag" => op,
+			"cid" => conf.id.as_str(),
+			"ck" => conf.nonce.as_str(),
+		};
+
+		let resp = self
+			.post("https://steamcommunity.com/mobileconf/ajaxop")
+			.form(&params)
+			.send()?;
+		let text = resp.text()?;
+		trace!("raw respond_to_confirmation response: {}", text);
+
+		let resp: SendConfirmationResponse = serde_json::from_str(text.as_str())?;
+		ensure!(resp.success, "steam rejected the confirmation response");
+
+		Ok(())
+	}
+}
+
+/// Async counterpart to `SteamApiClient`, built on `reqwest::Client` instead of
+/// `reqwest::blocking::Client` so it can be driven from an async runtime, e.g. to manage many
+/// accounts concurrently from a single process. Shares its cookie-jar and session-building logic
+/// with `SteamApiClient` so the two stay behavior-identical.
+#[derive(Debug)]
+pub struct AsyncSteamApiClient {
+	cookies: reqwest::cookie::Jar,
+	client: reqwest::Client,
+	pub session: Option<secrecy::Secret<Session>>,
+}
+
+impl AsyncSteamApiClient {
+	pub fn new(session: Option<secrecy::Secret<Session>>) -> AsyncSteamApiClient {
+		AsyncSteamApiClient {
+			cookies: reqwest::cookie::Jar::default(),
+			client: reqwest::ClientBuilder::new()
+				.cookie_store(true)
+				.user_agent("Mozilla/5.0 (Linux; U; Android 4.1.1; en-us; Google Nexus 4 - 4.1.1 - API 16 - 768x1280 Build/JRO03S) AppleWebKit/534.30 (KHTML, like Gecko) Version/4.0 Mobile Safari/534.30")
+				.default_headers(HeaderMap::from_iter(hashmap! {
+					HeaderName::from_str("X-Requested-With").expect("could not build default request headers") => HeaderValue::from_str("com.valvesoftware.android.steam.community").expect("could not build default request headers")
+				}.into_iter()))
+				.build()
+				.unwrap(),
+			session,
+		}
+	}
+
+	fn build_session(&self, data: &OAuthData) -> Session {
+		build_session_from_oauth(
+			data,
+			self.extract_session_id()
+				.expect("failed to extract session id from cookies"),
+		)
+	}
+
+	fn extract_session_id(&self) -> Option<String> {
+		extract_session_id(&self.cookies)
+	}
+
+	pub fn save_cookies_from_response(&mut self, response: &reqwest::Response) {
+		save_cookies_from_response(&self.cookies, response.headers());
+	}
+
+	pub fn request<U: reqwest::IntoUrl + std::fmt::Display>(
+		&self,
+		method: reqwest::Method,
+		url: U,
+	) -> reqwest::RequestBuilder {
+		trace!("making request: {} {}", method, url);
+		prime_request_cookies(
+			&self.cookies,
+			self.session.as_ref().map(|s| s.expose_secret()),
+		);
+
+		self.client
+			.request(method, url)
+			.header(COOKIE, self.cookies.cookies(&STEAM_COOKIE_URL).unwrap())
+	}
+
+	pub fn get<U: reqwest::IntoUrl + std::fmt::Display>(&self, url: U) -> reqwest::RequestBuilder {
+		self.request(reqwest::Method::GET, url)
+	}
+
+	pub fn post<U: reqwest::IntoUrl + std::fmt::Display>(&self, url: U) -> reqwest::RequestBuilder {
+		self.request(reqwest::Method::POST, url)
+	}
+
+	/// Updates the cookie jar with the session cookies by pinging steam servers.
+	pub async fn update_session(&mut self) -> anyhow::Result<()> {
+		trace!("AsyncSteamApiClient::update_session");
+
+		let resp = self
+			.get("https://steamcommunity.com/login?oauth_client_id=DE45CD61&oauth_scope=read_profile%20write_profile%20read_client%20write_client".parse::<Url>().unwrap())
+			.send().await?;
+		self.save_cookies_from_response(&resp);
+		trace!("{:?}", resp);
+
+		trace!("cookies: {:?}", self.cookies);
+		Ok(())
+	}
+
+	/// Pings an authenticated community page to check whether this client's session cookies are
+	/// still accepted by Steam; the OAuth token is not sent. Steam redirects unauthenticated requests to the
+	/// login page, so a redirect there means the session has expired. Useful for long-lived
+	/// processes to cheaply revalidate before a batch of operations instead of blindly
+	/// re-logging-in.
+	pub async fn is_session_valid(&self) -> bool {
+		match self.get("https://steamcommunity.com/my/profile").send().await {
+			Ok(resp) => !resp.url().as_str().contains("/login"),
+			Err(_) => false,
+		}
+	}
+
+	/// Re-derives the web session cookies from the stored OAuth token and rewrites this client's
+	/// `Secret<Session>` in place. Lets a caller recover from an expired session without a full
+	/// username/password login.
+	///
+	/// Host: api.steampowered.com
+	/// Endpoint: POST /IMobileAuthService/GetWGToken/v0001
+	pub async fn refresh_session(&mut self) -> anyhow::Result<()> {
+		ensure!(matches!(self.session, Some(_)));
+		let old_session = self.session.as_ref().unwrap().expose_secret().clone();
+
+		let params = hashmap! {
+			"access_token" => old_session.token.clone(),
+		};
+
+		let resp = self
+			.post(format!(
+				"{}/IMobileAuthService/GetWGToken/v0001",
+				STEAM_API_BASE.to_string()
+			))
+			.form(&params)
+			.send()
+			.await?;
+		let text = resp.text().await?;
+		trace!("raw refresh_session response: {}", text);
+
+		let resp: SteamApiResponse<RefreshSessionResponse> = serde_json::from_str(text.as_str())?;
+
+		self.session = Some(secrecy::Secret::new(Session {
+			steam_login: format!("{}%7C%7C{}", old_session.steam_id, resp.response.token),
+			steam_login_secure: format!(
+				"{}%7C%7C{}",
+				old_session.steam_id, resp.response.token_secure
+			),
+			..old_session
+		}));
+
+		Ok(())
+	}
+
+	async fn get_rsa_key(&self, username: &str) -> anyhow::Result<RsaResponse> {
+		let params = hashmap! {
+			"donotcache" => format!(
+				"{}",
+				SystemTime::now()
+					.duration_since(UNIX_EPOCH)
+					.unwrap()
+					.as_secs()
+					* 1000
+			),
+			"username" => username.to_string(),
+		};
+
+		let resp = self
+			.post("https://steamcommunity.com/login/getrsakey/")
+			.form(&params)
+			.send()
+			.await?;
+		let text = resp.text().await?;
+		trace!("raw get_rsa_key response: {}", text);
+
+		let resp: RsaResponse = serde_json::from_str(text.as_str())?;
+		ensure!(resp.success, "steam did not provide an rsa key for this account");
+
+		Ok(resp)
+	}
+
+	/// Endpoint: POST /login/dologin
+	pub async fn login(
+		&mut self,
+		username: String,
+		password: String,
+		twofactor_code: String,
+		email_code: String,
+		captcha_gid: String,
+		captcha_text: String,
+	) -> anyhow::Result<LoginResponse> {
+		let rsa = self.get_rsa_key(&username).await?;
+		let mut password = password;
+		let encrypted_password_result = encrypt_password(&rsa, &password);
+		password.zeroize();
+		let encrypted_password = encrypted_password_result?;
+
+		let params = hashmap! {
+			"donotcache" => format!(
+				"{}",
+				SystemTime::now()
+					.duration_since(UNIX_EPOCH)
+					.unwrap()
+					.as_secs()
+					* 1000
+			),
+			"username" => username,
+			"password" => encrypted_password,
+			"twofactorcode" => twofactor_code,
+			"emailauth" => email_code,
+			"captchagid" => captcha_gid,
+			"captcha_text" => captcha_text,
+			"rsatimestamp" => rsa.timestamp.clone(),
+			"remember_login" => "true".into(),
+			"oauth_client_id" => "DE45CD61".into(),
+			"oauth_scope" => "read_profile write_profile read_client write_client".into(),
+		};
+
+		let resp = self
+			.post("https://steamcommunity.com/login/dologin")
+			.form(&params)
+			.send()
+			.await?;
+		self.save_cookies_from_response(&resp);
+		let text = resp.text().await?;
+		trace!("raw login response: {}", text);
+
+		let login_resp: LoginResponse = serde_json::from_str(text.as_str())?;
+
+		if let Some(oauth) = &login_resp.oauth {
+			self.session = Some(secrecy::Secret::new(self.build_session(&oauth)));
+		}
+
+		Ok(login_resp)
+	}
+
+	/// Host: api.steampowered.com
+	/// Endpoint: POST /ITwoFactorService/AddAuthenticator/v0001
+	pub async fn add_authenticator(
+		&mut self,
+		device_id: String,
+	) -> anyhow::Result<AddAuthenticatorResponse> {
+		ensure!(matches!(self.session, Some(_)));
+		let params = hashmap! {
+			"access_token" => self.session.as_ref().unwrap().expose_secret().token.clone(),
+			"steamid" => self.session.as_ref().unwrap().expose_secret().steam_id.to_string(),
+			"authenticator_type" => "1".into(),
+			"device_identifier" => device_id,
+			"sms_phone_id" => "1".into(),
+		};
+
+		let resp = self
+			.post(format!(
+				"{}/ITwoFactorService/AddAuthenticator/v0001",
+				STEAM_API_BASE.to_string()
+			))
+			.form(&params)
+			.send()
+			.await?;
+		self.save_cookies_from_response(&resp);
+		let text = resp.text().await?;
+		trace!("raw add authenticator response: {}", text);
+
+		let resp: SteamApiResponse<AddAuthenticatorResponse> = serde_json::from_str(text.as_str())?;
+
+		Ok(resp.response)
+	}
+
+	/// Host: api.steampowered.com
+	/// Endpoint: POST /ITwoFactorService/FinalizeAddAuthenticator/v0001
+	pub async fn finalize_authenticator(
+		&self,
+		sms_code: String,
+		code_2fa: String,
+		time_2fa: u64,
+	) -> anyhow::Result<FinalizeAddAuthenticatorResponse> {
+		ensure!(matches!(self.session, Some(_)));
+		let params = hashmap! {
+			"steamid" => self.session.as_ref().unwrap().expose_secret().steam_id.to_string(),
+			"access_token" => self.session.as_ref().unwrap().expose_secret().token.clone(),
+			"activation_code" => sms_code,
+			"authenticator_code" => code_2fa,
+			"authenticator_time" => time_2fa.to_string(),
+		};
+
+		let resp = self
+			.post(format!(
+				"{}/ITwoFactorService/FinalizeAddAuthenticator/v0001",
+				STEAM_API_BASE.to_string()
+			))
+			.form(&params)
+			.send()
+			.await?;
+
+		let text = resp.text().await?;
+		trace!("raw finalize authenticator response: {}", text);
+
+		let resp: SteamApiResponse<FinalizeAddAuthenticatorResponse> =
+			serde_json::from_str(text.as_str())?;
+
+		Ok(resp.response)
+	}
+
+	/// Host: api.steampowered.com
+	/// Endpoint: POST /ITwoFactorService/RemoveAuthenticator/v0001
+	pub async fn remove_authenticator(
+		&self,
+		revocation_code: String,
+	) -> anyhow::Result<RemoveAuthenticatorResponse> {
+		let params = hashmap! {
+			"steamid" => self.session.as_ref().unwrap().expose_secret().steam_id.to_string(),
+			"steamguard_scheme" => "2".into(),
+			"revocation_code" => revocation_code,
+			"access_token" => self.session.as_ref().unwrap().expose_secret().token.to_string(),
+		};
+
+		let resp = self
+			.post(format!(
+				"{}/ITwoFactorService/RemoveAuthenticator/v0001",
+				STEAM_API_BASE.to_string()
+			))
+			.form(&params)
+			.send()
+			.await?;
+
+		let text = resp.text().await?;
+		trace!("raw remove authenticator response: {}", text);
+
+		let resp: SteamApiResponse<RemoveAuthenticatorResponse> =
+			serde_json::from_str(text.as_str())?;
+
+		Ok(resp.response)
+	}
+
+	/// Host: store.steampowered.com
+	/// Endpoint: POST /phone/validate
+	pub async fn phone_validate(&self, phone_number: &String) -> anyhow::Result<PhoneValidateResponse> {
+		let params = hashmap! {
+			"sessionID" => self.session.as_ref().unwrap().expose_secret().session_id.as_str(),
+			"phoneNumber" => phone_number.as_str(),
+		};
+
+		let resp = self
+			.client
+			.post("https://store.steampowered.com/phone/validate")
+			.form(&params)
+			.send()
+			.await?
+			.json::<PhoneValidateResponse>()
+			.await?;
+
+		Ok(resp)
+	}
+
+	/// Host: steamcommunity.com
+	/// Endpoint: GET /mobileconf/getlist
+	pub async fn get_confirmations(
+		&self,
+		device_id: &str,
+		identity_secret: &str,
+	) -> anyhow::Result<Vec<Confirmation>> {
+		ensure!(matches!(self.session, Some(_)));
+		let steamid = self.session.as_ref().unwrap().expose_secret().steam_id;
+		let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+		let hash = generate_confirmation_hash_for_time(time, "conf", identity_secret)?;
+
+		let resp = self
+			.get("https://steamcommunity.com/mobileconf/getlist")
+			.query(&[
+				("p", device_id),
+				("a", steamid.to_string().as_str()),
+				("k", hash.as_str()),
+				("t", time.to_string().as_str()),
+				("m", "react"),
+				("tag", "conf"),
+			])
+			.send()
+			.await?;
+		let text = resp.text().await?;
+		trace!("raw get_confirmations response: {}", text);
+
+		let resp: GetConfirmationsResponse = serde_json::from_str(text.as_str())?;
+		ensure!(resp.success, "steam rejected the confirmation list request");
+
+		Ok(resp.conf)
+	}
+
+	/// Host: steamcommunity.com
+	/// Endpoint: POST /mobileconf/ajaxop
+	pub async fn respond_to_confirmation(
+		&self,
+		device_id: &str,
+		identity_secret: &str,
+		conf: &Confirmation,
+		accept: bool,
+	) -> anyhow::Result<()> {
+		ensure!(matches!(self.session, Some(_)));
+		let steamid = self.session.as_ref().unwrap().expose_secret().steam_id;
+		let op = if accept { "allow" } else { "cancel" };
+		let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+		let hash = generate_confirmation_hash_for_time(time, op, identity_secret)?;
+
+		let params = hashmap! {
+			"op" => op,
+			"p" => device_id,
+			"a" => steamid.to_string().as_str(),
+			"k" => hash.as_str(),
+			"t" => time.to_string().as_str(),
+			"m" => "react",
+			"tag" => op,
+			"cid" => conf.id.as_str(),
+			"ck" => conf.nonce.as_str(),
+		};
+
+		let resp = self
+			.post("https://steamcommunity.com/mobileconf/ajaxop")
+			.form(&params)
+			.send()
+			.await?;
+		let text = resp.text().await?;
+		trace!("raw respond_to_confirmation response: {}", text);
+
+		let resp: SendConfirmationResponse = serde_json::from_str(text.as_str())?;
+		ensure!(resp.success, "steam rejected the confirmation response");
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_is_clock_jink_within_tolerance() {
+		assert!(!is_clock_jink(100, 90, 86400));
+	}
+
+	#[test]
+	fn test_is_clock_jink_past_threshold() {
+		assert!(is_clock_jink(100_000, 0, 86400));
+	}
+
+	#[test]
+	fn test_generate_confirmation_hash_for_time_known_vector() {
+		let hash =
+			generate_confirmation_hash_for_time(1700000000, "conf", "aGVsbG93b3JsZHNlY3JldA==")
+				.unwrap();
+		assert_eq!(hash, "MYoUocYbmkSiDmEitUFdDGj12UM=");
+	}
+
+	#[test]
+	fn test_generate_confirmation_hash_for_time_differs_per_tag() {
+		let conf_hash =
+			generate_confirmation_hash_for_time(1700000000, "conf", "aGVsbG93b3JsZHNlY3JldA==")
+				.unwrap();
+		let allow_hash =
+			generate_confirmation_hash_for_time(1700000000, "allow", "aGVsbG93b3JsZHNlY3JldA==")
+				.unwrap();
+		assert_ne!(conf_hash, allow_hash);
+	}
 }